@@ -1,33 +1,66 @@
 import { defineStore } from 'pinia'
 import { Color } from 'three'
 import { animate, interpolate } from 'popmotion'
-import initFlock, { BirdConfig, Flock } from '~/wasm/flock/pkg/flock'
+import initFlock, { BirdConfig, ColorMode, Flock } from '~/wasm/flock/pkg/flock'
 
 import { themeColors } from '~/tailwind/colors'
 import {
   DEFAULT_BIRD_ID,
   IBirdConfig,
-  MAX_FLOCK_SIZE, generateBirdId, generateRandomBirdConfig
+  MAX_FLOCK_SIZE, generateBirdId, generateRandomBirdConfig, generateSeed
 } from '~/utils/background/background'
+import { setRandomSource } from '~/utils/random'
+import { playBlip } from '~/utils/audio'
+import { Easing, easingFunction } from '~/utils/easing'
 
 export const useFlockStore = defineStore('flock', () => {
+  // editable targets, bound to the gui
   const birdConfigs = ref(new Map<string, IBirdConfig>())
+  // values currently in the wasm flock, these trail the targets
+  // while a randomization animation is running
+  const appliedBirdConfigs = ref(new Map<string, IBirdConfig>())
+  // species whose sliders are being dragged don't animate
+  const userControlledConfigIds = new Set<string>()
+  const configAnimations = new Map<string, { stop: () => void }>()
   const isDragging = ref(false)
   const isReady = ref(false)
   const updating = ref(false)
   const isRandomizeAnimationEnabled = ref(true)
+  const configEasing = ref(Easing.EaseInOut)
+  // cycles the species through the curated presets until the user interacts
+  const isDemoReelEnabled = ref(false)
+  const demoReelDwellSeconds = ref(8)
+  let demoReelStep = 0
+  let demoReelTimer: ReturnType<typeof setTimeout> | undefined
   const timeStep = ref(1.0)
+  const isSoundEnabled = ref(false)
+  const soundVolume = ref(0.1)
   const maxFlockSize = ref(MAX_FLOCK_SIZE)
+  // point the whole flock is gently drawn toward, in scene coordinates
+  const attractor = ref(null as { x: number; y: number } | null)
+  const attractorStrength = ref(0.2)
+  const colorMode = ref(ColorMode.Species)
+  const seed = ref(generateSeed())
+  // last scene dimensions, used to respawn birds after a reseed
+  let sceneSize = { width: 0, height: 0 }
   const flock: Ref<Flock> = ref({} as Flock)
 
-  async function init (): Promise<void> {
+  async function init (props: {
+    seed?: bigint;
+    viewWidth: number;
+    viewHeight: number;
+  }): Promise<void> {
     await initFlock()
+    seed.value = props.seed ?? seed.value
+    sceneSize = { width: props.viewWidth, height: props.viewHeight }
     flock.value = new Flock(
       // todo: determine number birds to add based on screen size and performance
       // const n = (view.value.viewPort.width * view.value.viewPort.height) / 500;
       maxFlockSize.value,
-      BigInt(new Date().getUTCMilliseconds())
+      seed.value
     )
+    // spawning and the randomization animation draw from the flock rng
+    setRandomSource(() => flock.value.random())
     addOrUpdateBirdConfig({
       id: generateBirdId(),
       probability: 20,
@@ -39,7 +72,9 @@ export const useFlockStore = defineStore('flock', () => {
       maxSpeed: 7,
       maxForce: 0.7,
       birdSize: 10,
-      birdColor: themeColors.secondary[400]
+      birdColor: themeColors.secondary[400],
+      maxCount: 0,
+      priority: 0
     } as IBirdConfig)
     addOrUpdateBirdConfig({
       id: DEFAULT_BIRD_ID,
@@ -52,18 +87,64 @@ export const useFlockStore = defineStore('flock', () => {
       maxSpeed: 5,
       maxForce: 0.33,
       birdSize: 12,
-      birdColor: themeColors.primary[500]
+      birdColor: themeColors.primary[500],
+      maxCount: 0,
+      priority: 0
     } as IBirdConfig)
+    // spawn through reseed so a seed from the url
+    // gives the same flock as pressing reseed with it
+    reseed(seed.value)
     isReady.value = true
   }
 
   function dispose () {
-    birdConfigs.value.forEach((birdConfig) => {
+    clearTimeout(demoReelTimer)
+    // the random source reads from the flock that is about to be freed
+    setRandomSource(Math.random)
+    appliedBirdConfigs.value.forEach((birdConfig) => {
       if (birdConfig) { birdConfig.wasmObject?.free() }
     })
     flock.value?.free()
   }
 
+  function reseed (newSeed: bigint) {
+    if (!flock.value) { return }
+    const oldFlock = flock.value
+    seed.value = newSeed
+    flock.value = new Flock(maxFlockSize.value, newSeed)
+    oldFlock.free()
+    applyAttractor()
+    updateColorMode(colorMode.value)
+    birdConfigs.value.forEach(birdConfig => addOrUpdateBirdConfig(birdConfig))
+    for (let i = 0; i < maxFlockSize.value; i++) {
+      addBirdAtRandomPosition({
+        viewWidth: sceneSize.width,
+        viewHeight: sceneSize.height
+      })
+    }
+  }
+
+  function setAttractor (position: { x: number; y: number } | null) {
+    attractor.value = position
+    applyAttractor()
+  }
+
+  function applyAttractor () {
+    if (!flock.value) { return }
+    flock.value.attractor_strength = attractorStrength.value
+    if (attractor.value) {
+      flock.value.set_attractor(attractor.value.x, attractor.value.y)
+    } else {
+      flock.value.clear_attractor()
+    }
+  }
+
+  function updateColorMode (mode: ColorMode) {
+    colorMode.value = mode
+    if (!flock.value) { return }
+    flock.value.color_mode = mode
+  }
+
   function updateMaxFlockSize (size: number) {
     if (!flock.value) { return }
     flock.value.max_flock_size = size
@@ -72,13 +153,24 @@ export const useFlockStore = defineStore('flock', () => {
   function addOrUpdateBirdConfig (
     params: IBirdConfig
   ) {
+    const target = birdConfigs.value.get(params.id)
+    if (target) {
+      Object.assign(target, params)
+    } else {
+      birdConfigs.value.set(params.id, params)
+    }
+    applyBirdConfig(params)
+  }
+
+  // push a config to the wasm flock without touching the editable target
+  function applyBirdConfig (params: IBirdConfig) {
     if (!flock.value) {
       throw new Error(
         "[background.vuex] cannot add config, flock doesn't exist."
       )
     }
     const color = new Color(params.birdColor)
-    params.wasmObject = BirdConfig.new(
+    const wasmObject = BirdConfig.new(
       params.id,
       params.probability,
       params.neighborDistance,
@@ -91,11 +183,65 @@ export const useFlockStore = defineStore('flock', () => {
       params.birdSize,
       color.r,
       color.g,
-      color.b
+      color.b,
+      params.maxCount,
+      params.priority
     )
-    if (!params.wasmObject) { throw new Error('wasm object could not be generated for bird config') }
-    birdConfigs.value.set(params.id, params)
-    flock.value.insert_bird_config(params.id, params.wasmObject)
+    if (!wasmObject) { throw new Error('wasm object could not be generated for bird config') }
+    appliedBirdConfigs.value.set(params.id, { ...params, wasmObject })
+    flock.value.insert_bird_config(params.id, wasmObject)
+  }
+
+  // any edit stops the species' running animation, and while a slider
+  // is dragged the next randomization skips it
+  function setUserControlled (configId: string, isUserControlled: boolean) {
+    interruptDemoReel()
+    configAnimations.get(configId)?.stop()
+    configAnimations.delete(configId)
+    if (isUserControlled) {
+      userControlledConfigIds.add(configId)
+    } else {
+      userControlledConfigIds.delete(configId)
+    }
+  }
+
+  function applyBirdConfigPreset (configId: string, presetName: string) {
+    const config = birdConfigs.value.get(configId)
+    if (!config) {
+      throw new Error(
+        '[background.vuex] cannot apply preset, cannot find matching config.'
+      )
+    }
+    const target = presetBirdConfig(config, presetName)
+    if (!target) {
+      throw new Error(
+        `[background.vuex] cannot apply preset, no preset named ${presetName}.`
+      )
+    }
+    setUserControlled(configId, false)
+    Object.assign(config, target)
+    addOrUpdateBirdConfig(config)
+  }
+
+  // the species with its behavior sliders taken from a preset. keeps the
+  // species identity (id, spawn probability, color), undefined if no
+  // preset has that name
+  function presetBirdConfig (config: IBirdConfig, presetName: string): IBirdConfig | undefined {
+    const preset = BirdConfig.preset(presetName)
+    if (!preset) { return undefined }
+    const target = {
+      ...config,
+      neighborDistance: preset.neighbor_distance,
+      desiredSeparation: preset.desired_separation,
+      separationMultiplier: preset.separation_multiplier,
+      alignmentMultiplier: preset.alignment_multiplier,
+      cohesionMultiplier: preset.cohesion_multiplier,
+      maxSpeed: preset.max_speed,
+      maxForce: preset.max_force,
+      birdSize: preset.bird_size
+    }
+    preset.free()
+    return target
   }
 
   function removeBirdConfig (configIdToRemove: string) {
@@ -110,24 +256,40 @@ export const useFlockStore = defineStore('flock', () => {
         '[background.vuex] cannot remove config, cannot find matching config.'
       )
     }
+    setUserControlled(configIdToRemove, false)
     flock.value.remove_bird_config(configIdToRemove)
     birdConfigs.value.delete(configIdToRemove)
+    appliedBirdConfigs.value.delete(configIdToRemove)
   }
   function updateFlock (props: {
     sceneWidth: number;
     sceneHeight: number;
     timeStep: number;
-    updateFlockGeometryCallback: (
-      vertices: Float32Array,
-      colors: Float32Array
-    ) => void;
+    updateFlockInstancesCallback: (instances: Float32Array) => void;
   }) {
     if (!flock.value) { return }
+    sceneSize = { width: props.sceneWidth, height: props.sceneHeight }
     flock.value.update(
       props.sceneWidth,
       props.sceneHeight,
       props.timeStep ? props.timeStep : timeStep.value,
-      props.updateFlockGeometryCallback
+      props.updateFlockInstancesCallback
+    )
+  }
+
+  // smaller birds chirp higher
+  function playSpawnSound (config: IBirdConfig) {
+    if (!isSoundEnabled.value) { return }
+    playBlip({
+      frequency: 1400 - config.birdSize * 40,
+      volume: soundVolume.value
+    })
+  }
+
+  // species that have not hit their max count yet
+  function spawnableBirdConfigs () {
+    return [...birdConfigs.value.values()].filter(config =>
+      !config.maxCount || flock.value.species_count(config.id) < config.maxCount
     )
   }
 
@@ -136,12 +298,15 @@ export const useFlockStore = defineStore('flock', () => {
     viewHeight: number;
   }) {
     if (!flock.value) { return }
-    const config = weightedRandom([...birdConfigs.value.values()])
+    const configs = spawnableBirdConfigs()
+    if (!configs.length) { return }
+    const config = weightedRandom(configs)
     flock.value.add_bird_at_random_position(
       config.id,
       props.viewWidth,
       props.viewHeight
     )
+    playSpawnSound(config)
   }
 
   function addBirdAtPosition (props: {
@@ -149,59 +314,133 @@ export const useFlockStore = defineStore('flock', () => {
     y: number;
   }) {
     if (!flock.value) { return }
-    const config = weightedRandom([...birdConfigs.value.values()])
+    const configs = spawnableBirdConfigs()
+    if (!configs.length) { return }
+    const config = weightedRandom(configs)
     flock.value.add_bird(config.id, props.x, props.y)
+    playSpawnSound(config)
   }
 
   function cycleAnimateBirdConfigs () {
-    if (!isRandomizeAnimationEnabled.value) { return }
+    if (!isRandomizeAnimationEnabled.value || isDemoReelEnabled.value) { return }
     birdConfigs.value.forEach((birdConfig) => {
-      const newBirdConfigTarget = {
-        ...generateRandomBirdConfig(),
-        id: birdConfig.id
-      }
-      const mapNeighborDistance = interpolate([0, 10000], [birdConfig.neighborDistance, newBirdConfigTarget.neighborDistance])
-      const mapDesiredSeparation = interpolate([0, 10000], [birdConfig.desiredSeparation, newBirdConfigTarget.desiredSeparation])
-      const mapSeparationMultiplier = interpolate([0, 10000], [birdConfig.separationMultiplier, newBirdConfigTarget.separationMultiplier])
-      const mapAlignmentMultiplier = interpolate([0, 10000], [birdConfig.alignmentMultiplier, newBirdConfigTarget.alignmentMultiplier])
-      const mapCohesionMultiplier = interpolate([0, 10000], [birdConfig.cohesionMultiplier, newBirdConfigTarget.cohesionMultiplier])
-      const mapMaxForce = interpolate([0, 10000], [birdConfig.maxForce, newBirdConfigTarget.maxForce])
-      const mapMaxSpeed = interpolate([0, 10000], [birdConfig.maxSpeed, newBirdConfigTarget.maxSpeed])
-      const mapBirdColor = interpolate([0, 10000], [birdConfig.birdColor, newBirdConfigTarget.birdColor])
-      animate({
-        from: 0,
-        to: 10000,
-        duration: 1000 * 2,
-        onUpdate: (latest) => {
-          addOrUpdateBirdConfig({
-            ...birdConfig,
-            neighborDistance: mapNeighborDistance(latest),
-            desiredSeparation: mapDesiredSeparation(latest),
-            separationMultiplier: mapSeparationMultiplier(latest),
-            alignmentMultiplier: mapAlignmentMultiplier(latest),
-            cohesionMultiplier: mapCohesionMultiplier(latest),
-            maxForce: mapMaxForce(latest),
-            maxSpeed: mapMaxSpeed(latest),
-            birdColor: mapBirdColor(latest)
-          })
-        }
-      })
+      if (userControlledConfigIds.has(birdConfig.id)) { return }
+      // species keep their size, only the demo reel's presets change it
+      animateBirdConfigTo(birdConfig, { ...generateRandomBirdConfig(), birdSize: birdConfig.birdSize })
+    })
+  }
+
+  // sets the editable target right away and eases the applied values toward it
+  function animateBirdConfigTo (birdConfig: IBirdConfig, target: IBirdConfig) {
+    const applied = appliedBirdConfigs.value.get(birdConfig.id) ?? { ...birdConfig }
+    Object.assign(birdConfig, {
+      neighborDistance: target.neighborDistance,
+      desiredSeparation: target.desiredSeparation,
+      separationMultiplier: target.separationMultiplier,
+      alignmentMultiplier: target.alignmentMultiplier,
+      cohesionMultiplier: target.cohesionMultiplier,
+      maxForce: target.maxForce,
+      maxSpeed: target.maxSpeed,
+      birdSize: target.birdSize,
+      birdColor: target.birdColor
+    })
+    // unclamped so the spring easing can carry past the target, but
+    // floored at zero since every animated slider is non-negative.
+    // color stays clamped
+    const overshootable = (range: number[]) => {
+      const map = interpolate([0, 10000], range, { clamp: false })
+      return (latest: number) => Math.max(0, map(latest))
+    }
+    const mapNeighborDistance = overshootable([applied.neighborDistance, birdConfig.neighborDistance])
+    const mapDesiredSeparation = overshootable([applied.desiredSeparation, birdConfig.desiredSeparation])
+    const mapSeparationMultiplier = overshootable([applied.separationMultiplier, birdConfig.separationMultiplier])
+    const mapAlignmentMultiplier = overshootable([applied.alignmentMultiplier, birdConfig.alignmentMultiplier])
+    const mapCohesionMultiplier = overshootable([applied.cohesionMultiplier, birdConfig.cohesionMultiplier])
+    const mapMaxForce = overshootable([applied.maxForce, birdConfig.maxForce])
+    const mapMaxSpeed = overshootable([applied.maxSpeed, birdConfig.maxSpeed])
+    const mapBirdSize = overshootable([applied.birdSize, birdConfig.birdSize])
+    const mapBirdColor = interpolate([0, 10000], [applied.birdColor, birdConfig.birdColor])
+    configAnimations.get(birdConfig.id)?.stop()
+    configAnimations.set(birdConfig.id, animate({
+      from: 0,
+      to: 10000,
+      duration: 1000 * 2,
+      ease: easingFunction(configEasing.value),
+      onUpdate: (latest) => {
+        applyBirdConfig({
+          ...birdConfig,
+          neighborDistance: mapNeighborDistance(latest),
+          desiredSeparation: mapDesiredSeparation(latest),
+          separationMultiplier: mapSeparationMultiplier(latest),
+          alignmentMultiplier: mapAlignmentMultiplier(latest),
+          cohesionMultiplier: mapCohesionMultiplier(latest),
+          maxForce: mapMaxForce(latest),
+          maxSpeed: mapMaxSpeed(latest),
+          birdSize: mapBirdSize(latest),
+          birdColor: mapBirdColor(latest)
+        })
+      },
+      onComplete: () => configAnimations.delete(birdConfig.id)
+    }))
+  }
+
+  function setDemoReelEnabled (isEnabled: boolean) {
+    isDemoReelEnabled.value = isEnabled
+    clearTimeout(demoReelTimer)
+    // always start from the first preset so the reel plays the same way
+    demoReelStep = 0
+    if (isEnabled) { advanceDemoReel() }
+  }
+
+  // any user input ends the unattended showcase
+  function interruptDemoReel () {
+    if (isDemoReelEnabled.value) { setDemoReelEnabled(false) }
+  }
+
+  // each step gives every species a different curated preset,
+  // so the whole flock shifts character together
+  function advanceDemoReel () {
+    const presetNames = BirdConfig.preset_names() as string[]
+    let i = demoReelStep
+    birdConfigs.value.forEach((birdConfig) => {
+      const target = presetBirdConfig(birdConfig, presetNames[i++ % presetNames.length])
+      if (target) { animateBirdConfigTo(birdConfig, target) }
     })
+    demoReelStep++
+    demoReelTimer = setTimeout(advanceDemoReel, demoReelDwellSeconds.value * 1000)
   }
 
   return {
     birdConfigs,
+    appliedBirdConfigs,
     isDragging,
     isRandomizeAnimationEnabled,
+    configEasing,
+    isDemoReelEnabled,
+    demoReelDwellSeconds,
+    setDemoReelEnabled,
+    interruptDemoReel,
     isReady,
     updating,
     timeStep,
+    isSoundEnabled,
+    soundVolume,
     maxFlockSize,
+    attractor,
+    attractorStrength,
+    setAttractor,
+    applyAttractor,
+    colorMode,
+    updateColorMode,
+    seed,
+    reseed,
     updateMaxFlockSize,
     flock,
     init,
     dispose,
     addOrUpdateBirdConfig,
+    applyBirdConfigPreset,
+    setUserControlled,
     removeBirdConfig,
     updateFlock,
     addBirdAtRandomPosition,
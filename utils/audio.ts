@@ -0,0 +1,29 @@
+// short synthesized blips over WebAudio, rate limited so dense
+// events (e.g. the initial flock spawn) don't become a wall of noise
+const MIN_BLIP_INTERVAL_MS = 40
+
+let context: AudioContext | undefined
+let lastBlipAt = 0
+
+export function playBlip (props: {
+  frequency: number;
+  volume: number;
+  durationMs?: number;
+}) {
+  if (props.volume <= 0) { return }
+  const now = performance.now()
+  if (now - lastBlipAt < MIN_BLIP_INTERVAL_MS) { return }
+  lastBlipAt = now
+  // browsers only allow audio after a user gesture, so create lazily
+  context = context ?? new AudioContext()
+  const end = context.currentTime + (props.durationMs ?? 60) / 1000
+  const oscillator = context.createOscillator()
+  const gain = context.createGain()
+  oscillator.type = 'triangle'
+  oscillator.frequency.value = props.frequency
+  gain.gain.setValueAtTime(props.volume, context.currentTime)
+  gain.gain.exponentialRampToValueAtTime(0.0001, end)
+  oscillator.connect(gain).connect(context.destination)
+  oscillator.start()
+  oscillator.stop(end)
+}
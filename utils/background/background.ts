@@ -16,6 +16,10 @@ export interface IBirdConfig {
   maxForce: number;
   birdSize: number;
   birdColor: ColorRepresentation;
+  // most live birds of this species, 0 means unlimited
+  maxCount: number;
+  // species with a higher priority are drawn on top
+  priority: number;
   wasmObject?: BirdConfig;
 }
 
@@ -27,6 +31,17 @@ export function generateBirdId () {
   return truncate(generateUUID(), 8)
 }
 
+export function generateSeed () {
+  return BigInt(Date.now())
+}
+
+// seeds come from the url / gui as text, the flock wants a u64
+export function parseSeed (value: unknown): bigint | undefined {
+  if (typeof value !== 'string' || !/^\d+$/.test(value)) { return undefined }
+  const seed = BigInt(value)
+  return BigInt.asUintN(64, seed) === seed ? seed : undefined
+}
+
 export function generateRandomBirdConfig (): IBirdConfig {
   return {
     id: generateBirdId(),
@@ -40,6 +55,8 @@ export function generateRandomBirdConfig (): IBirdConfig {
     maxSpeed: randomFromRange(0.001, 10),
     birdColor: '#' + generateRandomColor().getHexString(),
     birdSize: randomFromRange(3, 15),
+    maxCount: 0,
+    priority: 0,
     wasmObject: undefined
   }
 }
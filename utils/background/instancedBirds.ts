@@ -0,0 +1,82 @@
+import {
+  BufferAttribute,
+  InstancedBufferGeometry,
+  InstancedInterleavedBuffer,
+  InterleavedBufferAttribute,
+  ShaderMaterial
+} from 'three'
+
+// floats per bird written by `Flock.update`:
+// [x, y, heading, radius, r, g, b]
+export const BIRD_INSTANCE_STRIDE = 7
+
+// unit triangle outline, pairs of vertices are line segments
+// (a, b), (b, c), (c, a). rotated and scaled per instance on the gpu
+function unitTriangleOutline () {
+  const corners = [0, (4 * Math.PI) / 3, (2 * Math.PI) / 3]
+    .map(angle => [Math.cos(angle), Math.sin(angle), 0])
+  const [a, b, c] = corners
+  return new Float32Array([a, b, b, c, c, a].flat())
+}
+
+const vertexShader = `
+  attribute vec4 birdTransform;
+  attribute vec3 birdColor;
+  varying vec3 vColor;
+  void main() {
+    float c = cos(birdTransform.z);
+    float s = sin(birdTransform.z);
+    vec2 p = mat2(c, s, -s, c) * position.xy * birdTransform.w + birdTransform.xy;
+    vColor = birdColor;
+    gl_Position = projectionMatrix * modelViewMatrix * vec4(p, 0.0, 1.0);
+  }
+`
+
+// instance colors are linear (three's Color with color management on),
+// converted to the output color space like the built-in materials
+const fragmentShader = `
+  varying vec3 vColor;
+  void main() {
+    gl_FragColor = vec4(vColor, 1.0);
+    #include <colorspace_fragment>
+  }
+`
+
+export function createBirdsGeometry (maxBirds: number) {
+  const geometry = new InstancedBufferGeometry()
+  geometry.setAttribute('position', new BufferAttribute(unitTriangleOutline(), 3))
+  resizeBirdInstances(geometry, maxBirds)
+  return geometry
+}
+
+export function createBirdsMaterial () {
+  return new ShaderMaterial({ vertexShader, fragmentShader })
+}
+
+// allocates the instance buffer for up to maxBirds birds. only call when
+// the max flock size changes, the old gl buffers are freed by disposing
+export function resizeBirdInstances (geometry: InstancedBufferGeometry, maxBirds: number) {
+  geometry.dispose()
+  // adding a bird at the max can overshoot it by one before one is removed
+  const instances = new Float32Array((maxBirds + 1) * BIRD_INSTANCE_STRIDE)
+  const buffer = new InstancedInterleavedBuffer(instances, BIRD_INSTANCE_STRIDE)
+  geometry.setAttribute('birdTransform', new InterleavedBufferAttribute(buffer, 4, 0))
+  geometry.setAttribute('birdColor', new InterleavedBufferAttribute(buffer, 3, 4))
+  geometry.instanceCount = 0
+}
+
+// writes the live birds into the front of the instance buffer and
+// only uploads that range, the rest of the buffer is not drawn
+export function updateBirdInstances (geometry: InstancedBufferGeometry, instances: Float32Array) {
+  const count = instances.length / BIRD_INSTANCE_STRIDE
+  let data = (geometry.getAttribute('birdTransform') as InterleavedBufferAttribute).data
+  if (instances.length > data.array.length) {
+    resizeBirdInstances(geometry, count)
+    data = (geometry.getAttribute('birdTransform') as InterleavedBufferAttribute).data
+  }
+  (data.array as Float32Array).set(instances)
+  data.updateRange.offset = 0
+  data.updateRange.count = instances.length
+  data.needsUpdate = true
+  geometry.instanceCount = count
+}
@@ -0,0 +1,25 @@
+import { easeInOut, easeOut, linear } from 'popmotion'
+
+export enum Easing {
+  Linear = 'linear',
+  EaseInOut = 'easeInOut',
+  EaseOut = 'easeOut',
+  Spring = 'spring'
+}
+
+// damped oscillation that overshoots then settles,
+// still maps 0 -> 0 and 1 -> 1
+function spring (t: number) {
+  return 1 - Math.cos(t * Math.PI * 4.5) * Math.exp(-t * 6)
+}
+
+const easingFunctions: { [key in Easing]: (t: number) => number } = {
+  [Easing.Linear]: linear,
+  [Easing.EaseInOut]: easeInOut,
+  [Easing.EaseOut]: easeOut,
+  [Easing.Spring]: spring
+}
+
+export function easingFunction (easing: Easing) {
+  return easingFunctions[easing]
+}
@@ -1,7 +1,18 @@
 import { Color } from 'three'
 
+let randomSource: () => number = Math.random
+
+// route random draws through another generator (e.g. the flock's seeded rng)
+export function setRandomSource (source: () => number) {
+  randomSource = source
+}
+
+export function nextRandom () {
+  return randomSource()
+}
+
 export function randomFromRange (min: number, max: number) {
-  return Math.random() * (max - min) + min
+  return nextRandom() * (max - min) + min
 }
 
 export function randomIntFromRange (min: number, max: number) {
@@ -20,7 +31,7 @@ export type IWeightedArray<T extends { probability: number }> = Array<T>
 export function selectRandomFromWeightedArray<T extends {probability: number}> (array: IWeightedArray<T>) {
   let i
   let pickedValue
-  const randomNr = Math.random()
+  const randomNr = nextRandom()
   let threshold = 0
   for (i = 0; i < array.length; i++) {
     if (array[i].probability === -1) {
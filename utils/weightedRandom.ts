@@ -1,3 +1,5 @@
+import { nextRandom } from './random'
+
 export type IWeightedArray<T extends { probability: number }> = Array<T>
 
 export function weightedRandom<T extends {probability: number}> (items: IWeightedArray<T>): T {
@@ -25,7 +27,7 @@ export function weightedRandom<T extends {probability: number}> (items: IWeighte
   // - maxCumulativeWeight = 8
   // - range for the random number is [0...8]
   const maxCumulativeWeight = cumulativeWeights[cumulativeWeights.length - 1]
-  const randomNumber = maxCumulativeWeight * Math.random()
+  const randomNumber = maxCumulativeWeight * nextRandom()
 
   // Picking the random item based on its weight.
   // The items with higher weight will be picked more often.
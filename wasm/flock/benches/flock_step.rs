@@ -0,0 +1,45 @@
+// plain timing loops for the flock step, kept dependency free.
+// run with `cargo bench` (release profile).
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::time::Instant;
+
+    use flock::{BirdConfig, Flock};
+
+    const WIDTH: f32 = 1600.;
+    const HEIGHT: f32 = 900.;
+    const WARMUP_STEPS: usize = 10;
+    const STEPS: usize = 100;
+
+    fn bench_flock_step(size: usize) {
+        let mut flock = Flock::new(size, 42);
+        for name in ["calm", "tight swarm"] {
+            flock.insert_bird_config(name.to_string(), BirdConfig::preset(name).unwrap());
+        }
+        for i in 0..size {
+            let name = if i % 2 == 0 { "calm" } else { "tight swarm" };
+            flock.add_bird_at_random_position(name.to_string(), WIDTH, HEIGHT);
+        }
+        for _ in 0..WARMUP_STEPS {
+            flock.step(WIDTH, HEIGHT, 1.);
+        }
+        let start = Instant::now();
+        for _ in 0..STEPS {
+            flock.step(WIDTH, HEIGHT, 1.);
+        }
+        let per_step = start.elapsed() / STEPS as u32;
+        println!("flock step  birds={:>5}  {:>10.3?}/step", size, per_step);
+    }
+
+    pub fn run() {
+        for size in [100, 500, 1000, 2000, 5000] {
+            bench_flock_step(size);
+        }
+    }
+}
+
+fn main() {
+    #[cfg(not(target_arch = "wasm32"))]
+    native::run();
+}
@@ -1,6 +1,5 @@
 use kd_tree::{KdPoint, KdTree2};
 use nalgebra::{Vector2};
-use std::f32::consts::PI;
 
 use crate::utils::clamp_magnitude;
 
@@ -12,6 +11,8 @@ pub struct Bird {
     pub velocity: Vector2<f32>,
     pub acceleration: Vector2<f32>,
     pub config_id: String,
+    // neighbors found by the last update, used for density coloring
+    pub neighbor_count: usize,
 }
 
 impl KdPoint for Bird {
@@ -30,10 +31,13 @@ impl Bird {
         width: &f32,
         height: &f32,
         time_step: &f32,
+        attractor: Option<(&Vector2<f32>, f32)>,
     ) {
         // update flock forces
         let birds_to_follow = birds.within_radius(self, bird_config.neighbor_distance);
         let birds_to_avoid = birds.within_radius(self, bird_config.desired_separation);
+        // the query point is in the tree too, so don't count the bird itself
+        self.neighbor_count = birds_to_follow.len().saturating_sub(1);
         let sep = self.seperate(birds_to_avoid, bird_config) * bird_config.separation_multiplier;
         let ali =
             self.align(birds_to_follow.to_owned(), bird_config) * bird_config.alignment_multiplier;
@@ -42,6 +46,9 @@ impl Bird {
         self.acceleration += sep;
         self.acceleration += ali;
         self.acceleration += coh;
+        if let Some((target, strength)) = attractor {
+            self.acceleration += self.seek(target, bird_config) * strength;
+        }
         // physics update
         clamp_magnitude(&mut self.acceleration, bird_config.max_force);
         self.velocity += 0.5 * (self.acceleration * (time_step * *time_step));
@@ -127,29 +134,26 @@ impl Bird {
         target
     }
 
-    pub fn get_vertices(&self, bird_config: &BirdConfig) -> Vec<Vector2<f32>> {
-        let angle = self.velocity.angle(&Vector2::new(0., 1.));
-        let r = bird_config.bird_size / ((3 as f32).sqrt());    
-        let a = Vector2::new(
-            r * angle.cos(), r * angle.sin()
-        );
-        let b = Vector2::new(
-            r * (angle + ((4. * PI)/3.)).cos(),
-            r * (angle + ((4. * PI)/3.)).sin()
-        );
-        let c = Vector2::new(
-            r * (angle + ((2. * PI)/3.)).cos(),
-            r * (angle + ((2. * PI)/3.)).sin()
-        );
-        // pairs of vertices represent line segments
-        // (start vertex and end vertex of line)
-        // e.g. 3 pairs makes a triangle
-        [
-            a, b,
-            b, c,
-            c, a
-        ]
-        .map(|e| e + self.position)
-        .to_vec()
+    fn seek(&self, target: &Vector2<f32>, bird_config: &BirdConfig) -> Vector2<f32> {
+        let mut steer = target - self.position;
+        if steer.magnitude() > 0f32 {
+            steer = steer.normalize();
+            steer *= bird_config.max_speed;
+            steer -= self.velocity;
+            clamp_magnitude(&mut steer, bird_config.max_force);
+            return steer;
+        }
+        Vector2::new(0f32, 0f32)
+    }
+
+    // angle of the velocity, the gpu rotates the instanced
+    // triangle outline by this
+    pub fn heading(&self) -> f32 {
+        self.velocity.angle(&Vector2::new(0., 1.))
+    }
+
+    // circumradius of the equilateral triangle drawn for the bird
+    pub fn radius(&self, bird_config: &BirdConfig) -> f32 {
+        bird_config.bird_size / (3_f32).sqrt()
     }
 }
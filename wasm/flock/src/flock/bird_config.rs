@@ -15,8 +15,24 @@ pub struct BirdConfig {
     pub color_r: f32,
     pub color_g: f32,
     pub color_b: f32,
+    // most live birds of this species, 0 means unlimited
+    pub max_count: usize,
+    // species with a higher priority are drawn on top
+    pub priority: i32,
 }
 
+impl BirdConfig {
+    pub fn cap(&self) -> Option<usize> {
+        match self.max_count {
+            0 => None,
+            max_count => Some(max_count),
+        }
+    }
+}
+
+// names accepted by `BirdConfig::preset`
+const PRESET_NAMES: [&str; 5] = ["calm", "chaotic", "tight swarm", "scattered", "predator"];
+
 #[wasm_bindgen]
 impl BirdConfig {
 
@@ -30,6 +46,7 @@ impl BirdConfig {
         self.id = id;
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: String,
         probability: i32,
@@ -44,6 +61,8 @@ impl BirdConfig {
         color_r: f32,
         color_g: f32,
         color_b: f32,
+        max_count: usize,
+        priority: i32,
     ) -> BirdConfig {
         BirdConfig {
             id,
@@ -59,6 +78,106 @@ impl BirdConfig {
             color_r,
             color_g,
             color_b,
+            max_count,
+            priority,
         }
     }
-}
\ No newline at end of file
+
+    pub fn preset_names() -> js_sys::Array {
+        PRESET_NAMES.iter().map(|name| JsValue::from_str(name)).collect()
+    }
+
+    // hand tuned configs so new users get good looking behavior
+    // without fiddling every slider. the preset name is used as the id.
+    pub fn preset(name: &str) -> Option<BirdConfig> {
+        let config = match name {
+            "calm" => BirdConfig {
+                id: name.to_string(),
+                probability: 50,
+                neighbor_distance: 60.,
+                desired_separation: 30.,
+                separation_multiplier: 0.4,
+                alignment_multiplier: 0.8,
+                cohesion_multiplier: 0.4,
+                max_speed: 3.,
+                max_force: 0.15,
+                bird_size: 10.,
+                color_r: 0.56,
+                color_g: 0.76,
+                color_b: 0.83,
+                max_count: 0,
+                priority: 0,
+            },
+            "chaotic" => BirdConfig {
+                id: name.to_string(),
+                probability: 50,
+                neighbor_distance: 15.,
+                desired_separation: 20.,
+                separation_multiplier: 1.5,
+                alignment_multiplier: 0.05,
+                cohesion_multiplier: 0.05,
+                max_speed: 9.,
+                max_force: 0.9,
+                bird_size: 8.,
+                color_r: 0.94,
+                color_g: 0.87,
+                color_b: 0.49,
+                max_count: 0,
+                priority: 0,
+            },
+            "tight swarm" => BirdConfig {
+                id: name.to_string(),
+                probability: 50,
+                neighbor_distance: 80.,
+                desired_separation: 12.,
+                separation_multiplier: 0.3,
+                alignment_multiplier: 0.6,
+                cohesion_multiplier: 1.2,
+                max_speed: 5.,
+                max_force: 0.5,
+                bird_size: 6.,
+                color_r: 0.36,
+                color_g: 0.8,
+                color_b: 0.75,
+                max_count: 0,
+                priority: 0,
+            },
+            "scattered" => BirdConfig {
+                id: name.to_string(),
+                probability: 50,
+                neighbor_distance: 20.,
+                desired_separation: 120.,
+                separation_multiplier: 1.2,
+                alignment_multiplier: 0.1,
+                cohesion_multiplier: 0.01,
+                max_speed: 4.,
+                max_force: 0.3,
+                bird_size: 10.,
+                color_r: 0.76,
+                color_g: 0.76,
+                color_b: 0.76,
+                max_count: 0,
+                priority: 0,
+            },
+            "predator" => BirdConfig {
+                id: name.to_string(),
+                probability: 10,
+                neighbor_distance: 150.,
+                desired_separation: 60.,
+                separation_multiplier: 0.8,
+                alignment_multiplier: 0.05,
+                cohesion_multiplier: 1.5,
+                max_speed: 8.,
+                max_force: 0.6,
+                bird_size: 18.,
+                color_r: 0.86,
+                color_g: 0.24,
+                color_b: 0.2,
+                max_count: 0,
+                priority: 0,
+            },
+            _ => return None,
+        };
+        Some(config)
+    }
+}
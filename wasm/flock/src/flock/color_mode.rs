@@ -0,0 +1,13 @@
+use wasm_bindgen::prelude::*;
+
+// how each bird's color is picked when the flock geometry is emitted
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    // the species' configured color
+    Species,
+    // neighbor count, few neighbors are cool, many are hot
+    Density,
+    // velocity relative to the species' max speed
+    Speed,
+}
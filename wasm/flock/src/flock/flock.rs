@@ -8,16 +8,27 @@ use std::collections::HashMap;
 
 // use crate::utils::log;
 
-use crate::utils::log;
+use crate::utils::{heat_color, log};
 
-use super::{bird::Bird, bird_config::BirdConfig};
+use super::{bird::Bird, bird_config::BirdConfig, color_mode::ColorMode};
+
+// floats per bird in the instance buffer passed to js
+const INSTANCE_STRIDE: usize = 7;
+// neighbor count that maps to the hottest density color
+const DENSITY_SATURATION: f32 = 20.;
 
 #[wasm_bindgen]
 pub struct Flock {
     birds: kd_tree::KdTree2<Bird>,
     configs: HashMap<String, BirdConfig>,
+    species_counts: HashMap<String, usize>,
     rng: oorandom::Rand32,
+    seed: u64,
     max_flock_size: usize,
+    // global point every bird is gently drawn toward
+    attractor: Option<Vector2<f32>>,
+    attractor_strength: f32,
+    color_mode: ColorMode,
 }
 
 #[wasm_bindgen]
@@ -27,11 +38,27 @@ impl Flock {
         Flock {
             max_flock_size,
             configs: HashMap::new(),
+            species_counts: HashMap::new(),
             birds: kd_tree::KdTree2::build_by_ordered_float(Vec::new()),
             rng: oorandom::Rand32::new(seed),
+            seed,
+            attractor: None,
+            attractor_strength: 0.2,
+            color_mode: ColorMode::Species,
         }
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // draw from the flock's seeded rng so js side randomness
+    // (species selection, randomized configs) replays with the seed
+    pub fn random(&mut self) -> f32 {
+        self.rng.rand_float()
+    }
+
     #[wasm_bindgen(getter)]
     pub fn max_flock_size(&self) -> usize {
         self.max_flock_size
@@ -46,18 +73,71 @@ impl Flock {
                 let idx = self.rng.rand_range(0..(new_birds.len()) as u32);
                 new_birds.remove(idx as usize);
             }
-            self.birds = kd_tree::KdTree2::build_by_key(new_birds, |bird, k| {
-                ordered_float::OrderedFloat(bird.position[k])
-            });
+            self.rebuild(new_birds);
         }
         self.max_flock_size = new_max_flock_size;
     }
 
+    pub fn set_attractor(&mut self, x: f32, y: f32) {
+        self.attractor = Some(Vector2::new(x, y));
+    }
+
+    pub fn clear_attractor(&mut self) {
+        self.attractor = None;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn attractor_strength(&self) -> f32 {
+        self.attractor_strength
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_attractor_strength(&mut self, attractor_strength: f32) {
+        self.attractor_strength = attractor_strength;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn color_mode(&self) -> ColorMode {
+        self.color_mode
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_color_mode(&mut self, color_mode: ColorMode) {
+        self.color_mode = color_mode;
+    }
+
     #[wasm_bindgen(getter)]
     pub fn current_flock_size(&self) -> usize {
         self.birds.len()
     }
 
+    // indices of the birds strictly within radius of (x, y), in the order of
+    // `bird_position`. only valid until the next add_bird or update
+    pub fn neighbors_within(&self, x: f32, y: f32, radius: f32) -> Vec<usize> {
+        // the tree hands back references into its own slice,
+        // so each bird's offset in that slice is its index
+        let base = self.birds.as_ptr() as usize;
+        let mut indices: Vec<usize> = self
+            .birds
+            .within_radius(&[x, y], radius)
+            .into_iter()
+            .map(|bird| (bird as *const Bird as usize - base) / std::mem::size_of::<Bird>())
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    // [x, y] of the bird at an index returned by `neighbors_within`
+    pub fn bird_position(&self, idx: usize) -> Option<Vec<f32>> {
+        self.birds
+            .get(idx)
+            .map(|bird| vec![bird.position.x, bird.position.y])
+    }
+
+    pub fn species_count(&self, config_id: &str) -> usize {
+        self.species_counts.get(config_id).copied().unwrap_or(0)
+    }
+
     pub fn add_bird(&mut self, config_id: String, pos_x: f32, pos_y: f32) {
         // check the config exists
         if !self.configs.contains_key(&config_id) {
@@ -68,6 +148,12 @@ impl Flock {
             log(&err);
             throw_str(&err);
         }
+        // skip species that already hit their cap
+        if let Some(cap) = self.configs[&config_id].cap() {
+            if self.species_count(&config_id) >= cap {
+                return;
+            }
+        }
         let position = Vector2::new(pos_x, pos_y);
         let velocity = Vector2::new(-self.rng.rand_float(), self.rng.rand_float());
         let acceleration = Vector2::new(-self.rng.rand_float(), self.rng.rand_float());
@@ -78,17 +164,15 @@ impl Flock {
             velocity,
             acceleration,
             config_id,
+            neighbor_count: 0,
         });
         let num_birds = self.birds.len();
         // if oversized remove one from front of the vector
-        if num_birds > usize::from(self.max_flock_size) {
+        if num_birds > self.max_flock_size {
             let idx = self.rng.rand_range(0..self.birds.len() as u32);
             new_birds.remove(idx as usize);
         }
-        // rebuild tree
-        self.birds = kd_tree::KdTree2::build_by_key(new_birds, |bird, k| {
-            ordered_float::OrderedFloat(bird.position[k])
-        });
+        self.rebuild(new_birds);
     }
 
     pub fn add_bird_at_random_position(&mut self, config_id: String, width: f32, height: f32) {
@@ -108,62 +192,150 @@ impl Flock {
         self.configs.remove(&config_id);
     }
 
-    // we could also pass a js closure that updates vertex buffer
-    // todo: compare [but wasm **should** win]
-    // js closure passed in should update
-    // the flocks entity geometry
-    // given the vertices passed.
+    // js closure passed in should update the flock's
+    // instance buffer given the per bird instance data.
+    // each bird is INSTANCE_STRIDE floats:
+    // [x, y, heading, radius, r, g, b]
+    // the triangle outline itself lives on the gpu and
+    // is instanced per bird.
     pub fn update(
         &mut self,
         width: f32,
         height: f32,
         time_step: f32,
-        update_flock_geometry: &js_sys::Function,
+        update_flock_instances: &js_sys::Function,
     ) {
-        // for collecting vertices and colors
-        let mut vertices: Vec<f32> = Vec::new();
-        let mut colors: Vec<f32> = Vec::new();
-        // we need to store the current state of the flock
-        // (just position for each bird)
-        let new_flock = self
-            .birds
-            .clone()
-            .to_vec()
-            .iter_mut()
-            .filter_map(|bird| {
-                let bird_config: Option<&BirdConfig> = self.configs.get(&bird.config_id);
-                match bird_config {
-                    Some(bird_config) => {
-                        bird.update_bird(
-                            &self.birds,
-                            bird_config.clone(),
-                            &width,
-                            &height,
-                            &time_step,
-                        );
-                        for vertex in bird.get_vertices(bird_config) {
-                            vertices.push(vertex.x);
-                            vertices.push(vertex.y);
-                            vertices.push(0.);
-                            colors.push(bird_config.color_r);
-                            colors.push(bird_config.color_g);
-                            colors.push(bird_config.color_b);
-                        }
-                        Some(bird.to_owned())
-                    },
-                    _ => None
-                }
-            })
-            .collect();
+        let new_flock = self.step_birds(width, height, time_step);
+        // for collecting per bird transforms and colors
+        let mut instances: Vec<f32> = Vec::with_capacity(new_flock.len() * INSTANCE_STRIDE);
+        // later instances draw over earlier ones, so emit in priority order.
+        // the sort is stable so equal priorities keep the flock order, and
+        // is skipped entirely when every species shares a priority
+        let mut draw_order: Vec<&Bird> = new_flock.iter().collect();
+        let mut priorities = self.configs.values().map(|config| config.priority);
+        if let Some(first) = priorities.next() {
+            if priorities.any(|priority| priority != first) {
+                draw_order.sort_by_cached_key(|bird| self.configs[&bird.config_id].priority);
+            }
+        }
+        for bird in draw_order {
+            let bird_config = &self.configs[&bird.config_id];
+            let [r, g, b] = match self.color_mode {
+                ColorMode::Species => [bird_config.color_r, bird_config.color_g, bird_config.color_b],
+                ColorMode::Density => heat_color(bird.neighbor_count as f32 / DENSITY_SATURATION),
+                ColorMode::Speed => heat_color(bird.velocity.magnitude() / bird_config.max_speed.max(f32::EPSILON)),
+            };
+            instances.extend_from_slice(&[
+                bird.position.x,
+                bird.position.y,
+                bird.heading(),
+                bird.radius(bird_config),
+                r,
+                g,
+                b,
+            ]);
+        }
 
-        let js_vertices = js_sys::Float32Array::from(vertices.as_slice());
-        let js_colors = js_sys::Float32Array::from(colors.as_slice());
-        let e = update_flock_geometry.call2(&JsValue::null(), &js_vertices, &js_colors);
+        let js_instances = js_sys::Float32Array::from(instances.as_slice());
+        let e = update_flock_instances.call1(&JsValue::null(), &js_instances);
         if e.is_err() {
-            log("could not call js update vertex buffer function from rust");
+            log("could not call js update instance buffer function from rust");
+        }
+        self.rebuild(new_flock);
+    }
+}
+
+impl Flock {
+    // advance the flock without emitting geometry,
+    // for native harnesses that have no js to call back into
+    pub fn step(&mut self, width: f32, height: f32, time_step: f32) {
+        let new_flock = self.step_birds(width, height, time_step);
+        self.rebuild(new_flock);
+    }
+
+    // neighbor queries only read the current tree, so on native
+    // birds are updated in parallel. wasm is single threaded.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn step_birds(&self, width: f32, height: f32, time_step: f32) -> Vec<Bird> {
+        use rayon::prelude::*;
+        self.birds
+            .par_iter()
+            .filter_map(|bird| self.step_bird(bird, &width, &height, &time_step))
+            .collect()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn step_birds(&self, width: f32, height: f32, time_step: f32) -> Vec<Bird> {
+        self.birds
+            .iter()
+            .filter_map(|bird| self.step_bird(bird, &width, &height, &time_step))
+            .collect()
+    }
+
+    // birds whose config was removed are dropped
+    fn step_bird(&self, bird: &Bird, width: &f32, height: &f32, time_step: &f32) -> Option<Bird> {
+        let bird_config = self.configs.get(&bird.config_id)?;
+        let mut bird = bird.clone();
+        let attractor = self
+            .attractor
+            .as_ref()
+            .map(|attractor| (attractor, self.attractor_strength));
+        bird.update_bird(&self.birds, bird_config, width, height, time_step, attractor);
+        Some(bird)
+    }
+
+    // rebuild tree and per species counts
+    fn rebuild(&mut self, birds: Vec<Bird>) {
+        // runs every frame, so counts are zeroed rather than cleared and a
+        // key is only allocated the first time a species is seen
+        self.species_counts.values_mut().for_each(|count| *count = 0);
+        for bird in &birds {
+            match self.species_counts.get_mut(&bird.config_id) {
+                Some(count) => *count += 1,
+                None => {
+                    self.species_counts.insert(bird.config_id.clone(), 1);
+                }
+            }
         }
-        // rebuild tree
-        self.birds =
-            kd_tree::KdTree2::build_by_key(new_flock, |bird, k| OrderedFloat(bird.position[k]));
+        self.birds = kd_tree::KdTree2::build_by_key(birds, |bird, k| OrderedFloat(bird.position[k]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_flock() -> Flock {
+        let mut flock = Flock::new(100, 42);
+        let config = BirdConfig::new(
+            "test".to_string(), 50, 20., 10., 1., 1., 1., 3., 0.1, 10., 1., 1., 1., 0, 0,
+        );
+        flock.insert_bird_config("test".to_string(), config);
+        flock
+    }
+
+    #[test]
+    fn neighbors_within_returns_birds_in_radius() {
+        let mut flock = test_flock();
+        flock.add_bird("test".to_string(), 0., 0.);
+        flock.add_bird("test".to_string(), 3., 4.);
+        flock.add_bird("test".to_string(), 50., 50.);
+
+        let mut positions: Vec<Vec<f32>> = flock
+            .neighbors_within(0., 0., 5.5)
+            .into_iter()
+            .map(|idx| flock.bird_position(idx).unwrap())
+            .collect();
+        positions.sort_by(|a, b| a[0].total_cmp(&b[0]));
+        assert_eq!(positions, vec![vec![0., 0.], vec![3., 4.]]);
+
+        let far = flock.neighbors_within(50., 50., 1.);
+        assert_eq!(far.len(), 1);
+        assert_eq!(flock.bird_position(far[0]), Some(vec![50., 50.]));
+
+        // a bird exactly on the radius is not within it
+        assert_eq!(flock.neighbors_within(0., 0., 5.).len(), 1);
+        assert!(flock.neighbors_within(-100., -100., 10.).is_empty());
+        assert_eq!(flock.bird_position(3), None);
     }
 }
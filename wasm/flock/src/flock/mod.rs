@@ -1,3 +1,7 @@
 mod bird;
+#[allow(clippy::module_inception)]
 mod flock;
-mod bird_config;
\ No newline at end of file
+mod bird_config;
+mod color_mode;
+
+pub use self::{bird_config::BirdConfig, color_mode::ColorMode, flock::Flock};
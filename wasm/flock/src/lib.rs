@@ -1,6 +1,8 @@
 mod utils;
 mod flock;
 
+pub use crate::flock::{BirdConfig, ColorMode, Flock};
+
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
 #[cfg(feature = "wee_alloc")]
@@ -50,13 +50,42 @@ pub fn nearly_equal(a: f32, b: f32, epsilon: f32) -> bool {
 
     if a == b {
         // shortcut, handles infinities
-        return true;
+        true
     } else if a == 0.0 || b == 0.0 || !diff.is_normal() {
         // a or b is zero or both are extremely close to it
         // relative error is less meaningful here
-        return diff < (epsilon * f32::MIN);
+        diff < (epsilon * f32::MIN)
     } else {
         // use relative error
-        return diff / (abs_a + abs_b) < epsilon;
+        diff / (abs_a + abs_b) < epsilon
+    }
+}
+
+// cool to hot gradient (blue, cyan, yellow, red) for t in 0..=1.
+// stops are picked in srgb and the result is linear, matching the
+// species colors the renderer converts back to srgb on output
+pub fn heat_color(t: f32) -> [f32; 3] {
+    const STOPS: [[f32; 3]; 4] = [
+        [0.2, 0.4, 1.0],
+        [0.2, 0.9, 0.9],
+        [1.0, 0.9, 0.2],
+        [1.0, 0.2, 0.2],
+    ];
+    let scaled = t.clamp(0., 1.) * (STOPS.len() - 1) as f32;
+    let i = (scaled as usize).min(STOPS.len() - 2);
+    let f = scaled - i as f32;
+    let (a, b) = (STOPS[i], STOPS[i + 1]);
+    [
+        srgb_to_linear(a[0] + (b[0] - a[0]) * f),
+        srgb_to_linear(a[1] + (b[1] - a[1]) * f),
+        srgb_to_linear(a[2] + (b[2] - a[2]) * f),
+    ]
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
     }
 }